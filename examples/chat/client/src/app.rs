@@ -1,4 +1,5 @@
 use std::{
+    collections::VecDeque,
     fmt::{Debug, Display},
     future::Future,
     hash::Hash,
@@ -12,9 +13,8 @@ use egui::{Color32, DragValue, Grid, RichText, Ui};
 use egui_shortcuts::SimpleSpawner;
 use framework::{
     futures::{Sink, SinkExt, StreamExt},
-    io::FrameworkError,
     tarpc::client::RpcError,
-    ClientFramework,
+    BridgeHandle, ClientFramework, FrameworkError,
 };
 use poll_promise::Promise;
 use std::sync::mpsc::Receiver;
@@ -38,11 +38,20 @@ impl TemplateApp {
         let egui_ctx = cc.egui_ctx.clone();
 
         let sess = Promise::spawn_async(async move {
-            // Get framework and channel
+            // Get framework and channel. Prefer WebTransport/QUIC, but a corporate proxy, an
+            // older browser, or a plain TCP-only environment may not be able to establish it -
+            // in that case fall back to a plain WebSocket connection instead of giving up.
             let url = url::Url::parse("https://127.0.0.1:9090/")?;
-            let sess =
-                quic_session::client_session(&url, chat_common::CERTIFICATE.to_vec()).await?;
-            let (frame, channel) = ClientFramework::new(sess).await?;
+            let conn: framework::ClientConnection =
+                match quic_session::client_session(&url, chat_common::CERTIFICATE.to_vec()).await {
+                    Ok(sess) => sess.into(),
+                    Err(_) => {
+                        let ws_url = url::Url::parse("wss://127.0.0.1:9090/ws")?;
+                        let (ws, _) = tokio_tungstenite::connect_async(ws_url).await?;
+                        ws.into()
+                    }
+                };
+            let (frame, channel) = ClientFramework::new(conn).await?;
 
             // Get root client
             let newclient = ChatServiceClient::new(Default::default(), channel);
@@ -71,10 +80,139 @@ fn connection_status<T: Send, E: Debug + Send>(ui: &mut Ui, prom: &Promise<Resul
     };
 }
 
+/// How many messages a [`ChatSession`] keeps around before dropping the oldest. Keeps memory
+/// bounded in long-running rooms.
+const HISTORY_LIMIT: usize = 500;
+
+const CHAR_WIDTH_PX: f32 = 8.0; // Chosen arbitrarily!
+const ROW_HEIGHT_PX: f32 = 18.0; // Chosen arbitrarily!
+
+/// Estimates how many wrapped rows a message takes up at the given panel width, since egui
+/// doesn't give us exact wrapped line counts without a full layout pass.
+fn wrapped_rows(msg: &MessageMetaData, width: f32) -> usize {
+    let chars_per_row = ((width / CHAR_WIDTH_PX) as usize).max(1);
+    let total_chars = msg.username.len() + msg.msg.len() + 1;
+    (total_chars / chars_per_row).max(1)
+}
+
+/// Tracks which wrapped rows of a virtually-rendered message list are currently visible.
+/// `count` is the total number of wrapped rows across all messages (recomputed whenever the
+/// panel width changes, since wrapping depends on it); `offset` is the topmost visible row and
+/// `height` the number of rows the viewport can show.
+#[derive(Default)]
+struct ScrollState {
+    offset: usize,
+    height: usize,
+    count: usize,
+}
+
+impl ScrollState {
+    fn max_offset(&self) -> usize {
+        self.count.saturating_sub(self.height)
+    }
+
+    fn up(&mut self, n: usize) {
+        self.offset = self.offset.saturating_sub(n);
+    }
+
+    fn down(&mut self, n: usize) {
+        self.offset = (self.offset + n).min(self.max_offset());
+    }
+
+    fn at_bottom(&self) -> bool {
+        self.offset >= self.max_offset()
+    }
+
+    fn stick_to_bottom(&mut self) {
+        self.offset = self.max_offset();
+    }
+}
+
 struct ChatSession {
     tx: tokio::sync::mpsc::Sender<MessageMetaData>,
     rx: Receiver<MessageMetaData>,
-    received: Vec<MessageMetaData>,
+    received: VecDeque<MessageMetaData>,
+    scroll: ScrollState,
+    panel_width: f32,
+    // Keeps the underlying WebTransport stream open for as long as the session is; dropping this
+    // tears the bridge (and the forwarding tasks reading/writing through `tx`/`rx`) down.
+    _bridge: BridgeHandle,
+}
+
+impl ChatSession {
+    fn new(
+        tx: tokio::sync::mpsc::Sender<MessageMetaData>,
+        rx: Receiver<MessageMetaData>,
+        bridge: BridgeHandle,
+    ) -> Self {
+        Self {
+            tx,
+            rx,
+            received: VecDeque::new(),
+            scroll: ScrollState::default(),
+            panel_width: 0.0,
+            _bridge: bridge,
+        }
+    }
+
+    fn push(&mut self, msg: MessageMetaData) {
+        let was_at_bottom = self.scroll.at_bottom();
+
+        if self.received.len() >= HISTORY_LIMIT {
+            self.received.pop_front();
+        }
+        self.received.push_back(msg);
+
+        self.recompute_count();
+
+        if was_at_bottom {
+            self.scroll.stick_to_bottom();
+        }
+    }
+
+    /// Recomputes the total wrapped row count for the current `panel_width`. Call whenever the
+    /// width changes, in addition to whenever a message is pushed.
+    fn recompute_count(&mut self) {
+        self.scroll.count = self
+            .received
+            .iter()
+            .map(|msg| wrapped_rows(msg, self.panel_width))
+            .sum();
+    }
+
+    fn set_panel_width(&mut self, width: f32) {
+        if width != self.panel_width {
+            self.panel_width = width;
+            self.recompute_count();
+        }
+    }
+
+    /// The messages whose wrapped rows overlap the current scroll window.
+    ///
+    /// Each message is rendered as a whole, so one straddling the bottom edge of the window can
+    /// still draw a few rows past it - there's no way around that without laying out and clipping
+    /// individual wrapped lines. What this does guarantee is that rendering stops accumulating
+    /// further messages once the window's row budget is spent, rather than the whole remaining
+    /// history being pulled in if several long messages in a row happened to overlap it.
+    fn visible(&self) -> impl Iterator<Item = &MessageMetaData> {
+        let (offset, height) = (self.scroll.offset, self.scroll.height);
+        let mut row = 0;
+        let mut shown = 0;
+
+        self.received.iter().filter(move |msg| {
+            if shown >= height {
+                return false;
+            }
+
+            let rows = wrapped_rows(msg, self.panel_width);
+            let visible = row + rows > offset && row < offset + height;
+            row += rows;
+            if visible {
+                shown += rows;
+            }
+            visible
+        })
+    }
 }
 
 impl eframe::App for TemplateApp {
@@ -106,18 +244,29 @@ impl eframe::App for TemplateApp {
                                 ui.horizontal(|ui| {
                                     ui.label(format!("{name} {}", desc.long_desc));
 
-                                    if ui.button("Connect").clicked() {
+                                    // The WebSocket fallback only carries the root tarpc channel
+                                    // (see `ClientFramework::supports_typed_bistreams`), so there's
+                                    // no way to open the per-room chat bistream over it - grey the
+                                    // button out rather than let the user hit a connect error.
+                                    let can_chat = sess.frame.supports_typed_bistreams();
+                                    let connect_button =
+                                        ui.add_enabled(can_chat, egui::Button::new("Connect"));
+                                    if !can_chat {
+                                        connect_button.on_hover_text(
+                                            "Chat isn't available over the WebSocket fallback connection - only room listing is.",
+                                        );
+                                    } else if connect_button.clicked() {
                                         let ctx = framework::tarpc::context::current();
                                         let client_clone = sess.client.clone();
 
                                         rooms_spawner.reset(ui);
 
                                         let name = name.clone();
-                                        let frame = sess.frame.clone();
+                                        let mut frame = sess.frame.clone();
                                         chat_spawner.spawn(ui, async move {
-                                            let stream = client_clone.chat(ctx, name).await??;
-                                            let stream = frame.connect_bistream(stream).await?;
-                                            let (mut sink, mut stream) = stream.split();
+                                            let handle = client_clone.chat(ctx, name).await??;
+                                            let (mut sink, mut stream, bridge) =
+                                                handle.connect(&mut frame).await?;
 
                                             let (loop_tx, rx) = std::sync::mpsc::channel();
                                             tokio::spawn(async move {
@@ -137,11 +286,7 @@ impl eframe::App for TemplateApp {
                                                 Ok::<_, anyhow::Error>(())
                                             });
 
-                                            let chat_sess = ChatSession {
-                                                tx,
-                                                rx,
-                                                received: vec![],
-                                            };
+                                            let chat_sess = ChatSession::new(tx, rx, bridge);
 
                                             Ok::<_, anyhow::Error>(chat_sess)
                                         });
@@ -159,11 +304,31 @@ impl eframe::App for TemplateApp {
                     Ok(chat_sess) => {
                         ui.strong("Connected to chat");
 
+                        chat_sess.set_panel_width(ui.available_width());
+                        chat_sess.scroll.height = (ui.available_height() / ROW_HEIGHT_PX) as usize;
+
                         for msg in chat_sess.rx.try_iter() {
-                            chat_sess.received.push(msg);
+                            chat_sess.push(msg);
+                        }
+
+                        let page = chat_sess.scroll.height.max(1);
+                        if ui.input(|i| i.key_pressed(egui::Key::PageUp)) {
+                            chat_sess.scroll.up(page);
+                        }
+                        if ui.input(|i| i.key_pressed(egui::Key::PageDown)) {
+                            chat_sess.scroll.down(page);
+                        }
+                        // Deliberately `scroll_delta`, not `smooth_scroll_delta`: we're stepping
+                        // `scroll` by whole rows, so we want the raw per-frame delta rather than
+                        // the eased-for-animation one.
+                        let scroll_rows = (ui.input(|i| i.scroll_delta.y) / ROW_HEIGHT_PX) as isize;
+                        if scroll_rows > 0 {
+                            chat_sess.scroll.up(scroll_rows as usize);
+                        } else if scroll_rows < 0 {
+                            chat_sess.scroll.down((-scroll_rows) as usize);
                         }
 
-                        for msg in &chat_sess.received {
+                        for msg in chat_sess.visible() {
                             ui.horizontal(|ui| {
                                 let [r, g, b] = msg.user_color;
                                 ui.label(
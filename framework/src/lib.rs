@@ -2,20 +2,37 @@ pub use serde;
 pub use tarpc;
 pub use futures;
 
+mod rooms;
+pub use rooms::{Rooms, SubId};
+
 use bytes::Bytes;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use std::convert::Infallible;
-use std::{marker::PhantomData, sync::Arc, task::Poll};
+use std::collections::HashMap;
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
 use tarpc::{transport::channel::UnboundedChannel, Transport};
 use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream, ReadHalf, SimplexStream, WriteHalf};
-use tokio_util::codec::{Decoder, LengthDelimitedCodec};
+use tokio::sync::oneshot;
+use tokio_util::codec::{Decoder, Framed, LengthDelimitedCodec};
 
-use futures::{AsyncRead, Sink, SinkExt, Stream, StreamExt};
+use futures::{future::Either, AsyncRead, Sink, SinkExt, Stream, StreamExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use web_transport::{RecvStream, SendStream, Session};
 
 pub struct Framework {
     pub sess: Session,
     pub next_id: usize,
+    pub compression: CompressionConfig,
+    demux: Arc<Demux>,
+    /// Keeps the root channel's bridge alive once [`Framework::accept_root_channel`] has accepted
+    /// it - otherwise it'd be torn down the moment the returned `Transport` is handed off, since
+    /// nothing else would be holding its [`BridgeHandle`].
+    _root_bridge: Option<BridgeHandle>,
 }
 
 /// Don't worry about it
@@ -23,38 +40,452 @@ pub struct Framework {
 unsafe impl Send for Framework {}
 
 impl Framework {
+    /// Spawns the single task that will ever call `sess.accept_bi()` for this session - see
+    /// [`Demux`]. Cloning a [`Framework`] (e.g. [`Framework::fork`]) shares this same task instead
+    /// of spawning another one, since two acceptors racing on the same session is exactly the bug
+    /// a centralized demux exists to avoid.
     pub fn new(sess: Session) -> Self {
-        Self { sess, next_id: 0 }
+        let demux = Arc::new(Demux::default());
+        tokio::spawn(run_acceptor(sess.clone(), demux.clone()));
+
+        Self {
+            sess,
+            next_id: FIRST_ALLOCATABLE_ID,
+            compression: CompressionConfig::default(),
+            demux,
+            _root_bridge: None,
+        }
+    }
+
+    /// A cheap clone for handing to a task that needs its own id counter (e.g.
+    /// [`Framework::send_byte_stream`]'s spawned sender) without spawning a second accept loop on
+    /// the same session.
+    ///
+    /// Resets `next_id`, so it's only safe because nothing a fork is used for today allocates a
+    /// new id of its own (`send_byte_stream`'s fork only `accept`s the handle its parent already
+    /// allocated). If a future caller needs to `open_typed_bistream` from a fork, share the
+    /// counter (e.g. an `Arc<AtomicUsize>`) instead of copying it, or ids will collide with the
+    /// parent's.
+    fn fork(&self) -> Self {
+        Self {
+            sess: self.sess.clone(),
+            next_id: FIRST_ALLOCATABLE_ID,
+            compression: self.compression,
+            demux: self.demux.clone(),
+            _root_bridge: None,
+        }
+    }
+
+    fn get_next_id(&mut self) -> usize {
+        let next = self.next_id + 1;
+        std::mem::replace(&mut self.next_id, next)
+    }
+
+    /// Reserves a new stream id and returns a handle that can be sent to the peer (e.g. as the
+    /// return value of an RPC method). The peer connects it with [`TypedBiStream::connect`]; call
+    /// [`TypedBiStream::accept`] on the returned handle once that happens.
+    pub fn open_typed_bistream<CTS, STC>(&mut self) -> TypedBiStream<CTS, STC> {
+        TypedBiStream::new(BiStream(self.get_next_id()))
+    }
+
+    /// Accepts the peer's root tarpc channel - the one opened by [`ClientFramework::new`]'s
+    /// WebTransport arm - off this session's [`Demux`], the same way any other [`TypedBiStream`]
+    /// is accepted. It's tagged with the reserved [`ROOT_BISTREAM`] id rather than one from
+    /// [`Framework::open_typed_bistream`]'s counter, since the client allocates it before it has
+    /// anything to negotiate an id over.
+    pub async fn accept_root_channel<Req: DeserializeOwned, Resp: Serialize>(
+        &mut self,
+    ) -> Result<impl Transport<Resp, Req, Error = FrameworkError>, FrameworkError> {
+        let (framed, bridge) = self.demux.wait_for(ROOT_BISTREAM).await?;
+        self._root_bridge = Some(bridge);
+        Ok(typed_protocol::<Req, Resp>(framed, self.compression))
+    }
+
+    /// Reserves a [`ByteStreamHandle`] and, once the peer connects it, drains `body` into it chunk
+    /// by chunk. The next chunk is only pulled once the current one has been fully written, so a
+    /// slow peer naturally back-pressures `body` instead of it being buffered ahead unboundedly.
+    /// Returns the handle immediately so it can be attached to an RPC response before the transfer
+    /// itself has started, alongside a [`BridgeHandle`] for the spawned sender task - if `body`,
+    /// `accept`, or a `send` errors, or the peer simply never connects, that's otherwise silently
+    /// swallowed by the spawn and the transfer just stalls with no signal to the caller. Drop it
+    /// to cancel the transfer early, or `join` it to find out how it ended.
+    pub fn send_byte_stream(
+        &mut self,
+        body: impl Stream<Item = Result<Bytes, FrameworkError>> + Send + 'static,
+    ) -> (ByteStreamHandle, BridgeHandle) {
+        let handle = self.open_typed_bistream::<Never, Vec<u8>>();
+        let mut fr = self.fork();
+
+        let task = tokio::spawn(async move {
+            let (mut sink, _stream, bridge) = handle.accept(&mut fr).await?;
+            futures::pin_mut!(body);
+
+            while let Some(chunk) = body.next().await {
+                let chunk = chunk?;
+                if chunk.is_empty() {
+                    continue; // an empty chunk would read as the terminator on the other side
+                }
+                sink.send(chunk.to_vec()).await?;
+            }
+            sink.send(Vec::new()).await?; // zero-length terminator frame
+
+            // Half-close and let the bridge forward what's still buffered before it's dropped.
+            sink.close().await?;
+            bridge.join().await?;
+
+            Ok::<_, FrameworkError>(())
+        });
+
+        (handle, BridgeHandle { task })
+    }
+}
+
+/// Either of the two transports a [`ClientFramework`] can be built on top of: a WebTransport/QUIC
+/// session, or (for deployment targets that can't establish one - corporate proxies, older
+/// browsers, plain TCP-only environments) a plain WebSocket connection.
+pub enum ClientConnection {
+    WebTransport(Session),
+    WebSocket(WebSocketStream<MaybeTlsStream<TcpStream>>),
+}
+
+impl From<Session> for ClientConnection {
+    fn from(sess: Session) -> Self {
+        Self::WebTransport(sess)
+    }
+}
+
+impl From<WebSocketStream<MaybeTlsStream<TcpStream>>> for ClientConnection {
+    fn from(ws: WebSocketStream<MaybeTlsStream<TcpStream>>) -> Self {
+        Self::WebSocket(ws)
+    }
+}
+
+/// What a [`ClientFramework`] actually has left once its root RPC channel has been carved out of
+/// the [`ClientConnection`] it was built from.
+#[derive(Clone)]
+enum ClientTransport {
+    /// A QUIC session can keep opening further bidirectional streams, so it's kept around for
+    /// [`TypedBiStream::connect`].
+    WebTransport(Session),
+    /// A WebSocket connection is fully consumed by the root channel; there's nothing left to open
+    /// additional streams over.
+    WebSocket,
+}
+
+/// The client-side counterpart to [`Framework`]. Keeps its own id counter since ids are only
+/// required to be unique within the side that allocated them.
+///
+/// `Clone`, so a single connection can be shared across every widget/task that needs to open its
+/// own [`TypedBiStream`] off it - the root bridge is kept alive in an `Arc` rather than torn down
+/// the moment one clone is dropped.
+#[derive(Clone)]
+pub struct ClientFramework {
+    transport: ClientTransport,
+    pub next_id: usize,
+    pub compression: CompressionConfig,
+    _root_bridge: Option<Arc<BridgeHandle>>,
+}
+
+impl ClientFramework {
+    /// Builds the client framework plus its root tarpc transport, accepting either a
+    /// WebTransport/QUIC session or (as a fallback) a WebSocket connection - see
+    /// [`ClientConnection`]. This is the transport you hand to your service's generated `Client`.
+    pub async fn new<Req: Serialize, Resp: DeserializeOwned>(
+        conn: impl Into<ClientConnection>,
+    ) -> Result<(Self, impl Transport<Req, Resp, Error = FrameworkError>), FrameworkError> {
+        let compression = CompressionConfig::default();
+
+        match conn.into() {
+            ClientConnection::WebTransport(mut sess) => {
+                let socks = sess.open_bi().await?;
+                let (transport, handle) = open_root_channel(socks, compression).await?;
+
+                let this = Self {
+                    transport: ClientTransport::WebTransport(sess),
+                    next_id: FIRST_ALLOCATABLE_ID,
+                    compression,
+                    _root_bridge: Some(Arc::new(handle)),
+                };
+                Ok((this, Either::Left(transport)))
+            }
+            ClientConnection::WebSocket(ws) => {
+                let this = Self {
+                    transport: ClientTransport::WebSocket,
+                    next_id: FIRST_ALLOCATABLE_ID,
+                    compression,
+                    _root_bridge: None,
+                };
+                Ok((this, Either::Right(websocket_protocol(ws, compression))))
+            }
+        }
     }
 
     fn get_next_id(&mut self) -> usize {
         let next = self.next_id + 1;
         std::mem::replace(&mut self.next_id, next)
     }
+
+    /// Whether this connection can open additional [`TypedBiStream`]s via
+    /// [`TypedBiStream::connect`] - only true for the WebTransport/QUIC transport. The WebSocket
+    /// fallback only has the root tarpc channel to offer; callers that need a side channel (a
+    /// chat room, a byte stream, ...) should check this first and fall back to root-channel-only
+    /// behavior, rather than finding out via a [`FrameworkError::WebSocketUnsupported`] failure.
+    pub fn supports_typed_bistreams(&self) -> bool {
+        matches!(self.transport, ClientTransport::WebTransport(_))
+    }
+
+    /// Returns the underlying QUIC session, or an error if this framework was built over a
+    /// WebSocket fallback connection instead, which can't open further bidirectional streams.
+    fn session(&mut self) -> Result<&mut Session, FrameworkError> {
+        match &mut self.transport {
+            ClientTransport::WebTransport(sess) => Ok(sess),
+            ClientTransport::WebSocket => Err(FrameworkError::WebSocketUnsupported),
+        }
+    }
+
+    /// Connects a [`ByteStreamHandle`] received from the peer (e.g. as part of an RPC response)
+    /// and returns the chunks as they arrive, stopping at the zero-length terminator frame.
+    pub async fn recv_byte_stream(
+        &mut self,
+        handle: ByteStreamHandle,
+    ) -> Result<ByteStream, FrameworkError> {
+        let (_sink, stream, bridge) = handle.connect(self).await?;
+
+        let chunks = futures::stream::unfold(stream, |mut stream| async move {
+            match stream.next().await {
+                Some(Ok(chunk)) if chunk.is_empty() => None,
+                Some(Ok(chunk)) => Some((Ok(Bytes::from(chunk)), stream)),
+                Some(Err(e)) => Some((Err(e), stream)),
+                None => None,
+            }
+        });
+
+        Ok(Box::pin(KeepAlive {
+            inner: Box::pin(chunks),
+            _bridge: bridge,
+        }))
+    }
+}
+
+/// An uninhabited marker type standing in for "nothing will ever flow this direction" on a
+/// [`TypedBiStream`] that only carries data one way. `std::convert::Infallible` would be the
+/// obvious choice, but serde doesn't implement `Serialize`/`Deserialize` for it, and
+/// `TypedBiStream::accept`/`connect` require both type parameters to implement the relevant one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Never {}
+
+/// A handle to a [`ByteStream`] exchanged between peers (e.g. as part of an RPC request/response)
+/// so the chunks can be pulled on the other side. Multiplexed over its own [`BiStream`] id the
+/// same way [`TypedBiStream`] is; nothing flows the other direction, hence [`Never`].
+pub type ByteStreamHandle = TypedBiStream<Never, Vec<u8>>;
+
+/// The chunks of a streaming-body payload too large to buffer into a single bincode frame (a file
+/// upload, media, ...), produced by [`Framework::send_byte_stream`] / consumed by
+/// [`ClientFramework::recv_byte_stream`].
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, FrameworkError>> + Send>>;
+
+/// Keeps a [`BridgeHandle`] alive for as long as the stream wrapping it is, so the underlying
+/// WebTransport stream isn't torn down the moment `accept`/`connect`'s other return values are
+/// dropped.
+struct KeepAlive<S> {
+    inner: S,
+    _bridge: BridgeHandle,
+}
+
+impl<S: Stream + Unpin> Stream for KeepAlive<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
 }
 
 /// Internal type representing the identity of a connection between client and server
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 struct BiStream(usize);
 
-/*
+/// Reserved id for the root tarpc channel opened by [`ClientFramework::new`]'s WebTransport arm
+/// and accepted with [`Framework::accept_root_channel`]. Every bi-stream - root channel included -
+/// now starts with a [`BiStream`] header frame so [`run_acceptor`] can demux it; the root channel
+/// just uses this fixed id instead of one from [`Framework::open_typed_bistream`]'s counter, since
+/// the client opens it before there's anywhere to learn an allocated id from.
+const ROOT_BISTREAM: BiStream = BiStream(0);
+
+/// The first id [`Framework::get_next_id`]/[`ClientFramework::get_next_id`] hand out, reserving
+/// `0` ([`ROOT_BISTREAM`]) so an ordinary [`TypedBiStream`] can never collide with the root
+/// channel.
+const FIRST_ALLOCATABLE_ID: usize = 1;
+
+/// A physical WebTransport stream that's arrived but hasn't been claimed by a matching
+/// [`TypedBiStream::accept`] yet, or a waiter for one that hasn't arrived yet - see [`Demux`].
+enum Slot {
+    Ready(Framed<DuplexStream, LengthDelimitedCodec>, BridgeHandle),
+    Waiting(oneshot::Sender<(Framed<DuplexStream, LengthDelimitedCodec>, BridgeHandle)>),
+}
+
+/// Centralizes demultiplexing of incoming WebTransport streams by [`BiStream`] id.
+///
+/// Only [`run_acceptor`] ever calls `sess.accept_bi()`; every [`TypedBiStream::accept`] just
+/// registers itself as waiting for its id and awaits a slot being filled. This matters because a
+/// [`Framework`] (and its forks, e.g. the one [`Framework::send_byte_stream`] spawns) can have
+/// several typed streams being accepted concurrently - if each one called `accept_bi()` itself,
+/// whichever woke up first would see *some* incoming stream, and had no way to tell whether it was
+/// the one meant for it or one meant for a different concurrent acceptor; discarding a mismatched
+/// stream would permanently lose it while its real acceptor waited forever. Routing every incoming
+/// stream through one loop and a per-id slot map means a stream is only ever handed to the accept
+/// call it actually belongs to.
+#[derive(Default)]
+struct Demux {
+    slots: Mutex<HashMap<BiStream, Slot>>,
+}
+
+impl Demux {
+    /// Waits for the stream tagged with `id` to arrive, returning it immediately if it already
+    /// has.
+    async fn wait_for(
+        &self,
+        id: BiStream,
+    ) -> Result<(Framed<DuplexStream, LengthDelimitedCodec>, BridgeHandle), FrameworkError> {
+        let rx = {
+            let mut slots = self.slots.lock().unwrap();
+            match slots.remove(&id) {
+                Some(Slot::Ready(framed, handle)) => return Ok((framed, handle)),
+                Some(slot @ Slot::Waiting(_)) => {
+                    // Another accept() is already waiting on this id; put it back and let the
+                    // caller error out rather than silently stealing its slot.
+                    slots.insert(id, slot);
+                    return Err(FrameworkError::Closed);
+                }
+                None => {
+                    let (tx, rx) = oneshot::channel();
+                    slots.insert(id, Slot::Waiting(tx));
+                    rx
+                }
+            }
+        };
+
+        rx.await.map_err(|_| FrameworkError::Closed)
+    }
+
+    /// Dispatches a just-accepted, header-read stream to whoever is waiting on `id`, or buffers it
+    /// as [`Slot::Ready`] if nobody's asked for it yet.
+    fn deliver(
+        &self,
+        id: BiStream,
+        framed: Framed<DuplexStream, LengthDelimitedCodec>,
+        handle: BridgeHandle,
+    ) {
+        let mut slots = self.slots.lock().unwrap();
+        match slots.remove(&id) {
+            Some(Slot::Waiting(tx)) => {
+                // If the waiter already gave up, its accept() call is gone for good; drop the
+                // stream along with it rather than re-buffering for a claim that'll never come.
+                let _ = tx.send((framed, handle));
+            }
+            _ => {
+                slots.insert(id, Slot::Ready(framed, handle));
+            }
+        }
+    }
+}
+
+/// The single loop that ever calls `sess.accept_bi()` for a given [`Framework`] (and its forks),
+/// reading each incoming stream's header id and routing it through [`Demux::deliver`]. Runs until
+/// the session itself is gone.
+async fn run_acceptor(mut sess: Session, demux: Arc<Demux>) {
+    loop {
+        let socks = match sess.accept_bi().await {
+            Ok(socks) => socks,
+            Err(_) => return,
+        };
+
+        let (duplex, handle) = webtransport_futures_bridge(socks);
+        let mut framed = frame_duplex(duplex);
+
+        let id: BiStream = match framed.next().await {
+            Some(Ok(header)) => match decode(&header) {
+                Ok(id) => id,
+                Err(_) => continue, // malformed header; drop this stream and keep accepting
+            },
+            _ => continue, // stream closed before sending its header; drop and keep accepting
+        };
+
+        demux.deliver(id, framed, handle);
+    }
+}
+
 /// Uniquely identifies a stream, and carries type information about its contents.
 /// This is the type used to transmit information between client and server about the identity of a
 /// connected stream/sink combo.
 ///
 /// This is a type you should return from your API, in order to get a bidirectional stream on the other end.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct TypedBiStream<ClientToServer, ServerToClient> {
     id: BiStream,
     _phantom: PhantomData<(ClientToServer, ServerToClient)>,
 }
 
 impl<CTS, STC> TypedBiStream<CTS, STC> {
-    pub async fn accept(&self, fr: &mut Framework) -> Box<dyn Stream<CTS> + Sink<STC>> {
-        todo!()
+    fn new(id: BiStream) -> Self {
+        Self {
+            id,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<CTS: DeserializeOwned, STC: Serialize> TypedBiStream<CTS, STC> {
+    /// Waits for the peer to open the WebTransport stream matching this handle's id, then
+    /// returns the split sink/stream pair, typed and demultiplexed off the session.
+    ///
+    /// Call this from the side that created the handle with [`Framework::open_typed_bistream`].
+    /// Demultiplexing itself happens centrally in [`run_acceptor`] - this just waits on the slot
+    /// for `self.id` - so this can safely be awaited concurrently from several handles at once
+    /// without any of them stealing a physical stream meant for another.
+    pub async fn accept(
+        &self,
+        fr: &mut Framework,
+    ) -> Result<
+        (
+            impl Sink<STC, Error = FrameworkError>,
+            impl Stream<Item = Result<CTS, FrameworkError>>,
+            BridgeHandle,
+        ),
+        FrameworkError,
+    > {
+        let (framed, handle) = fr.demux.wait_for(self.id).await?;
+        let (sink, stream) = typed_protocol::<CTS, STC>(framed, fr.compression).split();
+        Ok((sink, stream, handle))
+    }
+}
+
+impl<CTS: Serialize, STC: DeserializeOwned> TypedBiStream<CTS, STC> {
+    /// Opens a fresh WebTransport stream, tags it with this handle's id as the first frame, and
+    /// returns the split sink/stream pair.
+    ///
+    /// Call this from the side connecting to a handle received from the peer.
+    pub async fn connect(
+        &self,
+        fr: &mut ClientFramework,
+    ) -> Result<
+        (
+            impl Sink<CTS, Error = FrameworkError>,
+            impl Stream<Item = Result<STC, FrameworkError>>,
+            BridgeHandle,
+        ),
+        FrameworkError,
+    > {
+        let socks = fr.session()?.open_bi().await?;
+        let (duplex, handle) = webtransport_futures_bridge(socks);
+        let mut framed = frame_duplex(duplex);
+
+        framed.send(Bytes::from(encode(&self.id)?)).await?;
+
+        let (sink, stream) = typed_protocol::<STC, CTS>(framed, fr.compression).split();
+        Ok((sink, stream, handle))
     }
 }
-*/
 
 const BUFFER_SIZE: usize = 4096; // Chosen arbitrarily!
 const MAX_READ_BYTES: usize = 4096; // Chosen arbitrarily!
@@ -63,51 +494,224 @@ const MAX_READ_BYTES: usize = 4096; // Chosen arbitrarily!
 #[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct TarpcBiStream(BiStream);
 
-/// Converts a webtransport bidirectional connection into a DuplexStream
-/// Warning: spawns tasks underneath
-pub fn webtransport_futures_bridge((mut tx, mut rx): (SendStream, RecvStream)) -> DuplexStream {
+/// Owns the tokio task(s) driving a [`webtransport_futures_bridge`]. Dropping it aborts the
+/// bridge immediately; awaiting [`BridgeHandle::join`] waits for it to wind down on its own (e.g.
+/// because the peer half-closed).
+pub struct BridgeHandle {
+    task: tokio::task::JoinHandle<Result<(), FrameworkError>>,
+}
+
+impl BridgeHandle {
+    /// Tears down the bridge immediately, closing both underlying WebTransport streams.
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+
+    /// Waits for the bridge to finish on its own. Useful when the caller wants to know once a
+    /// room/connection has actually gone away instead of just dropping its end.
+    pub async fn join(mut self) -> Result<(), FrameworkError> {
+        match (&mut self.task).await {
+            Ok(result) => result,
+            Err(_) => Ok(()), // aborted or panicked: the peer is already gone either way
+        }
+    }
+}
+
+impl Drop for BridgeHandle {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+/// Converts a webtransport bidirectional connection into a DuplexStream.
+///
+/// Spawns a task that drives both directions concurrently; it exits as soon as either direction
+/// sees a half-close (a `0`-byte read from the duplex, or `None` from the `RecvStream`), tearing
+/// down the other direction rather than leaking it. The returned [`BridgeHandle`] can be awaited
+/// to observe that shutdown, or dropped to force it early.
+pub fn webtransport_futures_bridge(
+    (mut tx, mut rx): (SendStream, RecvStream),
+) -> (DuplexStream, BridgeHandle) {
     let (proxy, ret) = tokio::io::duplex(BUFFER_SIZE);
 
     let (mut readhalf, mut writehalf) = tokio::io::split(proxy);
 
-    tokio::spawn(async move {
-        loop {
-            let mut buf = vec![0_u8; BUFFER_SIZE];
+    let task = tokio::spawn(async move {
+        let egress = async {
+            loop {
+                let mut buf = vec![0_u8; BUFFER_SIZE];
 
-            let n_bytes_read = readhalf.read(&mut buf).await?;
-            buf.truncate(n_bytes_read);
+                let n_bytes_read = readhalf.read(&mut buf).await?;
+                if n_bytes_read == 0 {
+                    break;
+                }
+                buf.truncate(n_bytes_read);
 
-            tx.write(&buf).await?;
-        }
+                tx.write(&buf).await?;
+            }
 
-        #[allow(unreachable_code)]
-        Ok::<_, FrameworkError>(())
-    });
+            tx.finish()?;
+            Ok::<_, FrameworkError>(())
+        };
 
-    tokio::spawn(async move {
-        loop {
-            if let Some(bytes) = rx.read(MAX_READ_BYTES).await? {
-                writehalf.write(bytes.as_ref()).await?;
+        let ingress = async {
+            loop {
+                match rx.read(MAX_READ_BYTES).await? {
+                    Some(bytes) => writehalf.write(bytes.as_ref()).await?,
+                    None => break,
+                };
             }
-        }
 
-        #[allow(unreachable_code)]
-        Ok::<_, FrameworkError>(())
+            Ok::<_, FrameworkError>(())
+        };
+
+        // Whichever direction finishes first wins the race; dropping the other future here
+        // cancels it and closes its half of the bridge (and, transitively, its WebTransport
+        // stream) instead of leaving it running forever.
+        tokio::select! {
+            result = egress => result,
+            result = ingress => result,
+        }
     });
 
-    ret
+    (ret, BridgeHandle { task })
 }
 
 pub fn webtransport_protocol<Rx: DeserializeOwned, Tx: Serialize>(
     socks: (SendStream, RecvStream),
+    compression: CompressionConfig,
+) -> (impl Transport<Tx, Rx, Error = FrameworkError>, BridgeHandle) {
+    let (duplex, handle) = webtransport_futures_bridge(socks);
+    (typed_protocol(frame_duplex(duplex), compression), handle)
+}
+
+/// Like [`webtransport_protocol`], but tags the stream with [`ROOT_BISTREAM`] as its first frame
+/// first, the same way [`TypedBiStream::connect`] tags an ordinary one - so [`run_acceptor`] can
+/// demux the peer's root tarpc channel through [`Framework::accept_root_channel`] instead of
+/// mistaking its first frame for an untagged stream's payload.
+async fn open_root_channel<Rx: DeserializeOwned, Tx: Serialize>(
+    socks: (SendStream, RecvStream),
+    compression: CompressionConfig,
+) -> Result<(impl Transport<Tx, Rx, Error = FrameworkError>, BridgeHandle), FrameworkError> {
+    let (duplex, handle) = webtransport_futures_bridge(socks);
+    let mut framed = frame_duplex(duplex);
+
+    framed.send(Bytes::from(encode(&ROOT_BISTREAM)?)).await?;
+
+    Ok((typed_protocol(framed, compression), handle))
+}
+
+/// Drives the same encode/decode plumbing as `webtransport_protocol`, but over a WebSocket
+/// connection instead of a `(SendStream, RecvStream)` pair. A WebSocket message already delimits
+/// one encoded value, so there's no need for the `LengthDelimitedCodec`/duplex bridge that QUIC
+/// streams require - each `Message::Binary` maps directly to one frame.
+pub fn websocket_protocol<Rx: DeserializeOwned, Tx: Serialize>(
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    compression: CompressionConfig,
 ) -> impl Transport<Tx, Rx, Error = FrameworkError> {
-    let duplex = webtransport_futures_bridge(socks);
+    ws.sink_map_err(FrameworkError::from)
+        .with(move |obj: Tx| async move { Ok(Message::Binary(encode_frame(&obj, compression)?)) })
+        .filter_map(|msg| async move {
+            match msg {
+                Ok(Message::Binary(bytes)) => Some(decode_frame::<Rx>(&bytes)),
+                // Ping/Pong are answered by tokio-tungstenite itself, Close just means the
+                // connection is winding down, and Text/Frame never carry a frame payload - none of
+                // these are a value for the caller, so skip them rather than failing to decode.
+                Ok(_) => None,
+                Err(e) => Some(Err(e.into())),
+            }
+        })
+}
+
+/// Wraps a raw duplex stream in the length-delimited framing shared by every protocol on top of
+/// `webtransport_futures_bridge`.
+fn frame_duplex(duplex: DuplexStream) -> Framed<DuplexStream, LengthDelimitedCodec> {
+    LengthDelimitedCodec::default().framed(duplex)
+}
 
-    LengthDelimitedCodec::default()
-        .framed(duplex)
+/// Layers encoding/decoding on top of an already-framed duplex stream. Used both by
+/// `webtransport_protocol` directly and by the tagged streams (`TypedBiStream` and the root
+/// channel), whose `BiStream` header frame is written (`TypedBiStream::connect`,
+/// `open_root_channel`) or consumed (`run_acceptor`) on the same `Framed` before the rest is
+/// handed to this layer.
+fn typed_protocol<Rx: DeserializeOwned, Tx: Serialize>(
+    framed: Framed<DuplexStream, LengthDelimitedCodec>,
+    compression: CompressionConfig,
+) -> impl Transport<Tx, Rx, Error = FrameworkError> {
+    framed
         .sink_map_err(FrameworkError::from)
-        .with(|obj: Tx| async move { Ok(Bytes::from(encode(&obj)?)) })
-        .map(|frame| Ok(decode::<Rx>(&frame?)?))
+        .with(move |obj: Tx| async move { Ok(Bytes::from(encode_frame(&obj, compression)?)) })
+        .map(|frame| Ok(decode_frame::<Rx>(&frame?)?))
+}
+
+/// Configures optional zstd compression of serialized frames. Frames stay wire-compatible either
+/// way: every frame is prefixed with a one-byte codec tag (see [`CODEC_RAW`]/[`CODEC_ZSTD`]), so a
+/// peer with compression disabled can still decode anything a peer with it enabled sends, and vice
+/// versa.
+#[derive(Clone, Copy, Debug)]
+pub struct CompressionConfig {
+    /// Serialized payloads at or under this many bytes are sent raw; compressing small frames
+    /// tends to cost more than it saves.
+    pub threshold: usize,
+    /// zstd compression level, passed straight through to `zstd::encode_all`.
+    pub level: i32,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 1024,
+            level: 3,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Every frame is sent raw (tagged [`CODEC_RAW`]); useful when payloads are already compressed
+    /// or small enough that the zstd frame/level overhead isn't worth it.
+    pub const fn disabled() -> Self {
+        Self {
+            threshold: usize::MAX,
+            level: 0,
+        }
+    }
+}
+
+const CODEC_RAW: u8 = 0;
+const CODEC_ZSTD: u8 = 1;
+
+fn encode_frame<T: Serialize>(
+    value: &T,
+    compression: CompressionConfig,
+) -> Result<Vec<u8>, FrameworkError> {
+    let raw = encode(value)?;
+
+    if raw.len() <= compression.threshold {
+        let mut frame = Vec::with_capacity(1 + raw.len());
+        frame.push(CODEC_RAW);
+        frame.extend_from_slice(&raw);
+        return Ok(frame);
+    }
+
+    let compressed = zstd::encode_all(raw.as_slice(), compression.level)
+        .map_err(|_| FrameworkError::Compression)?;
+    let mut frame = Vec::with_capacity(1 + compressed.len());
+    frame.push(CODEC_ZSTD);
+    frame.extend_from_slice(&compressed);
+    Ok(frame)
+}
+
+fn decode_frame<T: DeserializeOwned>(frame: &[u8]) -> Result<T, FrameworkError> {
+    let (&tag, payload) = frame.split_first().ok_or(FrameworkError::EmptyFrame)?;
+
+    match tag {
+        CODEC_RAW => Ok(decode(payload)?),
+        CODEC_ZSTD => {
+            let raw = zstd::decode_all(payload).map_err(|_| FrameworkError::Compression)?;
+            Ok(decode(&raw)?)
+        }
+        _ => Err(FrameworkError::UnknownCodec(tag)),
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -118,8 +722,26 @@ pub enum FrameworkError {
     #[error("Websocket")]
     WebSocket(#[from] web_transport::Error),
 
+    #[error("WebSocket")]
+    Tungstenite(#[from] tokio_tungstenite::tungstenite::Error),
+
     #[error("Duplex IO")]
     Io(#[from] std::io::Error),
+
+    #[error("Connection closed before stream negotiation completed")]
+    Closed,
+
+    #[error("This operation requires a WebTransport session, not the WebSocket fallback")]
+    WebSocketUnsupported,
+
+    #[error("Frame (de)compression failed")]
+    Compression,
+
+    #[error("Received an empty frame")]
+    EmptyFrame,
+
+    #[error("Unknown frame codec tag {0}")]
+    UnknownCodec(u8),
 }
 
 /// The encoding function for all data. Mostly for internal use, exposed here for debugging
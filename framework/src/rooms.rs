@@ -0,0 +1,99 @@
+//! A per-room broadcast subsystem: each room fans incoming messages out to every subscriber
+//! currently registered for it, pruning subscribers whose receiver has been dropped.
+//!
+//! A `chat`-style RPC handler registers a subscriber for the room it was asked to join, forwards
+//! whatever it reads off its inbound sink into [`Rooms::broadcast`], and streams the subscriber's
+//! [`Receiver`] back out to the caller.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+/// How many unsent messages a subscriber's channel can buffer before [`Rooms::broadcast`] starts
+/// waiting on it; bounds how far a slow client can make a room lag behind.
+const SUBSCRIBER_BUFFER: usize = 32;
+
+/// Identifies one subscriber within a room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubId(usize);
+
+/// A cloneable handle to the broadcast state for every room. Cloning is cheap - it's just another
+/// reference to the same `Arc<RwLock<..>>` map, so it can be shared across every connected `chat`
+/// bistream.
+pub struct Rooms<T> {
+    inner: Arc<RwLock<RoomsInner<T>>>,
+}
+
+impl<T> Clone for Rooms<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Default for Rooms<T> {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(RoomsInner {
+                rooms: HashMap::new(),
+                next_id: 0,
+            })),
+        }
+    }
+}
+
+struct RoomsInner<T> {
+    rooms: HashMap<String, HashMap<SubId, Sender<T>>>,
+    next_id: usize,
+}
+
+impl<T: Clone + Send + 'static> Rooms<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new subscriber for `room` and returns its id plus the receiving half of its
+    /// channel. The channel is bounded ([`SUBSCRIBER_BUFFER`]), so a slow subscriber bounds its own
+    /// buffering rather than the room's.
+    pub fn new_sub(&self, room: impl Into<String>) -> (SubId, Receiver<T>) {
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER);
+
+        let mut inner = self.inner.write().unwrap();
+        let id = SubId(inner.next_id);
+        inner.next_id += 1;
+        inner.rooms.entry(room.into()).or_default().insert(id, tx);
+
+        (id, rx)
+    }
+
+    /// Fans `msg` out to every subscriber currently registered for `room`, pruning any whose
+    /// receiver has since been dropped (closed channel) so disconnected clients are reaped instead
+    /// of accumulating forever. A no-op if the room has no subscribers.
+    pub async fn broadcast(&self, room: &str, msg: T) {
+        let subs: Vec<(SubId, Sender<T>)> = {
+            let inner = self.inner.read().unwrap();
+            match inner.rooms.get(room) {
+                Some(subs) => subs.iter().map(|(id, tx)| (*id, tx.clone())).collect(),
+                None => return,
+            }
+        };
+
+        let mut dead = Vec::new();
+        for (id, tx) in subs {
+            if tx.send(msg.clone()).await.is_err() {
+                dead.push(id);
+            }
+        }
+
+        if !dead.is_empty() {
+            let mut inner = self.inner.write().unwrap();
+            if let Some(subs) = inner.rooms.get_mut(room) {
+                for id in dead {
+                    subs.remove(&id);
+                }
+            }
+        }
+    }
+}